@@ -9,6 +9,13 @@
 //!
 //! The deployer can be created using [Env::deployer].
 //!
+//! Contracts deployed with [DeployerWithAddress::deploy_v2] can run their
+//! constructor atomically as part of the deployment. In tests,
+//! [Env::register_contract_with_constructor] uploads a Wasm contract,
+//! deploys it under a given deployer address, and runs its constructor with
+//! the given arguments in a single call, mirroring [Env::register_contract]
+//! for contracts that require constructor arguments.
+//!
 //! ### Examples
 //!
 //! ```
@@ -43,7 +50,8 @@
 //! ```
 
 use crate::{
-    env::internal::Env as _, unwrap::UnwrapInfallible, Address, Bytes, BytesN, Env, IntoVal,
+    env::internal::Env as _, unwrap::UnwrapInfallible, xdr::ScSpecEntry, Address, Bytes, BytesN,
+    Env, IntoVal, Map, String, Symbol, Val, Vec,
 };
 
 /// Deployer provides access to deploying contracts.
@@ -51,6 +59,18 @@ pub struct Deployer {
     env: Env,
 }
 
+/// Metadata describing Wasm code that has already been uploaded to the
+/// ledger, as returned by [Deployer::get_contract_code_metadata].
+pub struct ContractCodeMetadata {
+    /// The SDK/environment interface version the Wasm was built against.
+    pub env_interface_version: u64,
+    /// The decoded `contractmeta` entries embedded in the Wasm, as key/value
+    /// pairs.
+    pub meta: Map<String, String>,
+    /// The raw contract spec entries embedded in the Wasm.
+    pub spec: Vec<ScSpecEntry>,
+}
+
 impl Deployer {
     pub(crate) fn new(env: &Env) -> Deployer {
         Deployer { env: env.clone() }
@@ -129,6 +149,40 @@ impl Deployer {
             .into_val(&self.env)
     }
 
+    /// Returns metadata about the Wasm code already uploaded for
+    /// `wasm_hash`.
+    ///
+    /// This lets a contract, or tooling, inspect what it is about to run
+    /// before calling [DeployerWithAddress::deploy] on it: the
+    /// SDK/environment interface
+    /// version the code was built against, the decoded `contractmeta`
+    /// entries, and the raw contract spec entries, all read directly from
+    /// the uploaded Wasm's ledger entry rather than requiring a separate
+    /// download and offline parse of the Wasm.
+    pub fn get_contract_code_metadata(
+        &self,
+        wasm_hash: impl IntoVal<Env, BytesN<32>>,
+    ) -> ContractCodeMetadata {
+        let env = &self.env;
+        let wasm_hash_obj = wasm_hash.into_val(env).to_object();
+        let env_interface_version = env
+            .get_contract_code_interface_version(wasm_hash_obj)
+            .unwrap_infallible();
+        let meta = env
+            .get_contract_code_meta(wasm_hash_obj)
+            .unwrap_infallible()
+            .into_val(env);
+        let spec = env
+            .get_contract_code_spec(wasm_hash_obj)
+            .unwrap_infallible()
+            .into_val(env);
+        ContractCodeMetadata {
+            env_interface_version,
+            meta,
+            spec,
+        }
+    }
+
     /// Replaces the executable of the current contract with the provided Wasm.
     ///
     /// The Wasm blob identified by the `wasm_hash` has to be already present
@@ -159,6 +213,44 @@ impl Deployer {
             )
             .unwrap_infallible();
     }
+
+    /// Returns whether Wasm code with the given hash has already been
+    /// uploaded to the ledger.
+    ///
+    /// This doesn't fail when the code isn't present, unlike a deploy
+    /// against a missing hash would, so it supports idempotent deploy flows
+    /// that skip [Self::upload_contract_wasm] when the code is already
+    /// there.
+    pub fn has_contract_code(&self, wasm_hash: impl IntoVal<Env, BytesN<32>>) -> bool {
+        self.env
+            .has_contract_code(wasm_hash.into_val(&self.env).to_object())
+            .unwrap_infallible()
+    }
+
+    /// Returns whether a contract instance already exists at `contract_address`.
+    ///
+    /// Combined with [DeployerWithAddress::deployed_address], this supports
+    /// idempotent deploy flows: only deploy when the deterministic address
+    /// doesn't already have an instance.
+    pub fn has_contract_instance(&self, contract_address: Address) -> bool {
+        self.env
+            .has_contract_instance(contract_address.to_object())
+            .unwrap_infallible()
+    }
+
+    /// Returns the Wasm hash of the executable currently installed for the
+    /// contract instance at `contract_address`, if any.
+    ///
+    /// Many contracts can share a single uploaded Wasm blob, so this lets
+    /// callers inspect which code hash a deployed instance is currently
+    /// running, e.g. the current contract's own hash before deciding whether
+    /// to call [Self::update_current_contract_wasm].
+    pub fn get_contract_instance_wasm_hash(&self, contract_address: Address) -> Option<BytesN<32>> {
+        self.env
+            .get_contract_instance_wasm_hash(contract_address.to_object())
+            .unwrap_infallible()
+            .into_val(&self.env)
+    }
 }
 
 /// A deployer that deploys a contract that has its ID derived from the provided
@@ -188,16 +280,69 @@ impl DeployerWithAddress {
     ///
     /// Returns the deployed contract's address.
     pub fn deploy(&self, wasm_hash: impl IntoVal<Env, BytesN<32>>) -> Address {
+        self.deploy_v2(wasm_hash, Vec::new(&self.env))
+    }
+
+    /// Deploy a contract that uses Wasm executable with provided hash, and
+    /// invoke its constructor with the provided arguments.
+    ///
+    /// The address of the deployed contract is defined by the deployer address
+    /// and provided salt.
+    ///
+    /// The deployment and the constructor invocation happen in a single,
+    /// atomic host call, so the deployer's authorization covers both the
+    /// deployment and the constructor arguments, and the constructor can't be
+    /// front-run.
+    ///
+    /// Returns the deployed contract's address.
+    pub fn deploy_v2(
+        &self,
+        wasm_hash: impl IntoVal<Env, BytesN<32>>,
+        constructor_args: impl IntoVal<Env, Vec<Val>>,
+    ) -> Address {
         let env = &self.env;
         let address_obj = env
-            .create_contract(
+            .create_contract_with_constructor(
                 self.address.to_object(),
                 wasm_hash.into_val(env).to_object(),
                 self.salt.to_object(),
+                constructor_args.into_val(env).to_object(),
             )
             .unwrap_infallible();
         unsafe { Address::unchecked_new(env.clone(), address_obj) }
     }
+
+    /// Deploy a contract that uses Wasm executable with provided hash, then
+    /// invoke a named function on it with the provided arguments.
+    ///
+    /// This is the established pattern for initializing contracts that
+    /// predate constructors: the deployment and the initializer invocation
+    /// happen one after the other in the same invocation frame, so the
+    /// initializer can't be front-run.
+    ///
+    /// The deployer's authorization covers the deployment only. The function
+    /// is invoked as a separate call from the current contract afterwards,
+    /// and is not nested under the deployment's authorization.
+    ///
+    /// Returns the deployed contract's address together with the return
+    /// value of the invoked function.
+    pub fn deploy_and_invoke(
+        &self,
+        wasm_hash: impl IntoVal<Env, BytesN<32>>,
+        init_fn: Symbol,
+        init_args: impl IntoVal<Env, Vec<Val>>,
+    ) -> (Address, Val) {
+        let env = &self.env;
+        let address = self.deploy(wasm_hash);
+        let rv = env
+            .call(
+                address.to_object(),
+                init_fn.to_object(),
+                init_args.into_val(env).to_object(),
+            )
+            .unwrap_infallible();
+        (address, rv)
+    }
 }
 
 pub struct DeployerWithAsset {
@@ -224,3 +369,189 @@ impl DeployerWithAsset {
             .into_val(&self.env)
     }
 }
+
+#[cfg(any(test, feature = "testutils"))]
+impl Env {
+    /// Uploads `wasm`, deploys it under `deployer_address` and `salt`, and
+    /// runs its constructor with `constructor_args` — the `testutils`
+    /// counterpart of [Env::register_contract] for contracts that require
+    /// constructor arguments.
+    ///
+    /// `deployer_address` must already be authorized to cover the
+    /// deployment and its constructor invocation, e.g. via
+    /// [Env::mock_all_auths] or [Env::mock_auths] called before this. This
+    /// function doesn't mock auths itself, so it composes with tests that
+    /// rely on selective or negative auth mocking elsewhere in the same
+    /// `Env`.
+    ///
+    /// Returns the deployed contract's address, ready to exercise
+    /// immediately, e.g. with a generated `ContractClient`.
+    pub fn register_contract_with_constructor(
+        &self,
+        deployer_address: Address,
+        salt: impl IntoVal<Env, BytesN<32>>,
+        wasm: impl IntoVal<Env, Bytes>,
+        constructor_args: impl IntoVal<Env, Vec<Val>>,
+    ) -> Address {
+        let wasm_hash = self.deployer().upload_contract_wasm(wasm);
+        self.deployer()
+            .with_address(deployer_address, salt)
+            .deploy_v2(wasm_hash, constructor_args)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        testutils::{Address as _, AuthorizedFunction},
+        vec,
+    };
+
+    /// Generates a deployer address, mocks all auths, and returns a
+    /// [DeployerWithAddress] for that address and a salt derived from
+    /// `salt_byte`, shared by the tests below that exercise authorized
+    /// deployments.
+    fn setup(env: &Env, salt_byte: u8) -> (Address, DeployerWithAddress) {
+        env.mock_all_auths();
+        let deployer_address = Address::generate(env);
+        let salt = BytesN::from_array(env, &[salt_byte; 32]);
+        let deployer = env.deployer().with_address(deployer_address.clone(), salt);
+        (deployer_address, deployer)
+    }
+
+    #[test]
+    fn test_register_contract_with_constructor_deploys_and_runs_constructor() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let deployer_address = Address::generate(&env);
+        let salt = BytesN::from_array(&env, &[4; 32]);
+        let mock_wasm = [0u8; 0];
+        let constructor_args: Vec<Val> = Vec::new(&env);
+
+        let expected_address = env
+            .deployer()
+            .with_address(deployer_address.clone(), salt.clone())
+            .deployed_address();
+
+        let address = env.register_contract_with_constructor(
+            deployer_address.clone(),
+            salt,
+            mock_wasm.as_slice(),
+            constructor_args,
+        );
+
+        assert_eq!(address, expected_address);
+        assert_eq!(env.auths().len(), 1);
+        assert_eq!(env.auths()[0].0, deployer_address);
+    }
+
+    #[test]
+    fn test_deploy_v2_matches_deployed_address_and_authorizes_constructor() {
+        let env = Env::default();
+        let (deployer_address, deployer) = setup(&env, 0);
+
+        let mock_wasm = [0u8; 0];
+        let wasm_hash = env.deployer().upload_contract_wasm(mock_wasm.as_slice());
+
+        let expected_address = deployer.deployed_address();
+        let constructor_args: Vec<Val> = vec![&env, 1u32.into_val(&env)];
+        let deployed_address = deployer.deploy_v2(wasm_hash, constructor_args.clone());
+        assert_eq!(expected_address, deployed_address);
+
+        // `deployed_address` depends only on the deployer address and salt,
+        // so it keeps returning the very same deterministic address after
+        // the contract has been deployed.
+        assert_eq!(expected_address, deployer.deployed_address());
+
+        // The deployer authorized the deployment as a single invocation, and
+        // the constructor call/args are nested under it rather than being
+        // dropped or left unauthorized.
+        let auths = env.auths();
+        assert_eq!(auths.len(), 1);
+        let (auth_address, invocation) = &auths[0];
+        assert_eq!(auth_address, &deployer_address);
+        assert!(matches!(
+            invocation.function,
+            AuthorizedFunction::CreateContractHostFn(_)
+        ));
+        assert_eq!(invocation.sub_invocations.len(), 1);
+        assert_eq!(
+            invocation.sub_invocations[0].function,
+            AuthorizedFunction::Contract((
+                deployed_address,
+                Symbol::new(&env, "__constructor"),
+                constructor_args,
+            ))
+        );
+    }
+
+    #[test]
+    fn test_get_contract_code_metadata() {
+        let env = Env::default();
+
+        let mock_wasm = [0u8; 0];
+        let wasm_hash = env.deployer().upload_contract_wasm(mock_wasm.as_slice());
+
+        let metadata = env.deployer().get_contract_code_metadata(wasm_hash);
+        // The mock Wasm blob used in these tests carries no contractmeta or
+        // spec entries, so the decoded metadata for it is empty.
+        assert_eq!(metadata.env_interface_version, 0);
+        assert_eq!(metadata.meta.len(), 0);
+        assert_eq!(metadata.spec.len(), 0);
+    }
+
+    #[test]
+    fn test_deploy_and_invoke_returns_address_and_authorizes_deployment() {
+        let env = Env::default();
+        let (_deployer_address, deployer) = setup(&env, 1);
+
+        let mock_wasm = [0u8; 0];
+        let wasm_hash = env.deployer().upload_contract_wasm(mock_wasm.as_slice());
+
+        let expected_address = deployer.deployed_address();
+        let init_args: Vec<Val> = Vec::new(&env);
+        let (address, _init_rv) =
+            deployer.deploy_and_invoke(wasm_hash, Symbol::new(&env, "init"), init_args);
+        assert_eq!(address, expected_address);
+
+        // The deployer authorized the deployment itself as a single
+        // invocation.
+        let auths = env.auths();
+        assert_eq!(auths.len(), 1);
+        assert!(matches!(
+            auths[0].1.function,
+            AuthorizedFunction::CreateContractHostFn(_)
+        ));
+        // The `init` call is made separately after the deployment and is not
+        // nested under this authorization. The zero-byte mock Wasm used here
+        // exports no functions, so `init` makes no require_auth call and adds
+        // no authorization of its own.
+    }
+
+    #[test]
+    fn test_idempotent_deploy_flow_via_has_contract_code_and_instance() {
+        let env = Env::default();
+        let (_deployer_address, deployer) = setup(&env, 2);
+
+        let unrelated_wasm_hash = BytesN::from_array(&env, &[3; 32]);
+
+        // Nothing has been uploaded or deployed yet.
+        assert!(!env.deployer().has_contract_code(unrelated_wasm_hash));
+        assert!(!env
+            .deployer()
+            .has_contract_instance(deployer.deployed_address()));
+
+        let mock_wasm = [0u8; 0];
+        let wasm_hash = env.deployer().upload_contract_wasm(mock_wasm.as_slice());
+        assert!(env.deployer().has_contract_code(wasm_hash.clone()));
+
+        let address = deployer.deploy(wasm_hash.clone());
+        assert!(env.deployer().has_contract_instance(address.clone()));
+        assert_eq!(
+            env.deployer().get_contract_instance_wasm_hash(address),
+            Some(wasm_hash)
+        );
+    }
+}
@@ -0,0 +1,12 @@
+//! The Soroban SDK crate root.
+//!
+//! This tree only carries the `deploy` module, so only its public items are
+//! declared and re-exported here. The rest of the crate root (the `env`,
+//! `unwrap`, `testutils`, `xdr` modules and the `Address`/`Bytes`/`BytesN`/
+//! `Env`/`IntoVal`/`Map`/`String`/`Symbol`/`Val`/`Vec` types that `deploy.rs`
+//! builds on) lives elsewhere in the full crate and isn't part of this
+//! snapshot.
+
+mod deploy;
+
+pub use deploy::{ContractCodeMetadata, Deployer, DeployerWithAddress, DeployerWithAsset};